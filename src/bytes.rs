@@ -0,0 +1,270 @@
+// Copyright (c) 2016 rust-threshold-secret-sharing developers
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Byte-oriented API on top of `PackedSecretSharing`.
+//!
+//! The core scheme only ever shares vectors of field elements, leaving
+//! callers to encode their own data into `i64`s. This module instead shares
+//! a raw `&[u8]`: the byte stream is split into groups of `secret_count`
+//! field elements (padding the final group), and a digest of the original
+//! plaintext is shared alongside the payload so that `reconstruct_bytes`
+//! can detect a wrong or corrupted set of shares instead of silently
+//! returning garbage.
+
+extern crate sha3;
+
+use self::sha3::{Digest, Sha3_256};
+use numtheory::positivise;
+use packed::{PackedSecretSharing, PssError};
+
+/// Errors that can occur when sharing or reconstructing a byte slice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BytesError {
+    /// the underlying field-element sharing or reconstruction failed
+    Pss(PssError),
+    /// the digest recomputed from the reconstructed bytes did not match the
+    /// digest that was shared alongside them, meaning the shares used for
+    /// reconstruction were wrong or corrupted (too few shares are reported
+    /// as `Pss(PssError::NotEnoughShares)` instead)
+    DigestMismatch,
+    /// `reconstruct_bytes` was given no shares at all
+    NoShares,
+    /// the given shares did not all cover the same number of groups
+    InconsistentShareLengths,
+    /// the recombined length prefix does not leave enough elements for the
+    /// payload and digest it claims to be followed by, meaning the shares
+    /// used for reconstruction were wrong or corrupted
+    InvalidLength,
+}
+
+impl From<PssError> for BytesError {
+    fn from(error: PssError) -> BytesError {
+        BytesError::Pss(error)
+    }
+}
+
+const DIGEST_SIZE: usize = 32;
+/// the byte length of the plaintext is itself shared as a fixed-size `u64`,
+/// so it goes through the same `< prime` packing as every other byte and
+/// isn't silently reduced mod `prime` for payloads `>= prime` bytes long
+const LENGTH_SIZE: usize = 8;
+
+/// Largest `k` such that `256^k < prime`, i.e. how many bytes can be packed
+/// into a single field element without risking a value `>= prime`.
+fn bytes_per_element(prime: i64) -> usize {
+    let mut count = 0;
+    let mut limit: i64 = 1;
+    while limit.saturating_mul(256) < prime {
+        limit *= 256;
+        count += 1;
+    }
+    count
+}
+
+fn bytes_to_elements(bytes: &[u8], per_element: usize) -> Vec<i64> {
+    let pad = (per_element - (bytes.len() % per_element)) % per_element;
+    let mut padded = bytes.to_vec();
+    padded.extend(vec![0u8; pad]);
+    padded.chunks(per_element).map(|chunk| {
+        chunk.iter().fold(0i64, |acc, &byte| (acc << 8) | byte as i64)
+    }).collect()
+}
+
+fn elements_to_bytes(elements: &[i64], per_element: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(elements.len() * per_element);
+    for &element in elements {
+        for shift in (0..per_element).rev() {
+            bytes.push(((element >> (8 * shift)) & 0xff) as u8);
+        }
+    }
+    bytes
+}
+
+fn element_count_for(byte_len: usize, per_element: usize) -> usize {
+    (byte_len + per_element - 1) / per_element
+}
+
+impl PackedSecretSharing {
+    /// Shares an arbitrary byte slice, returning one vector of shares per
+    /// shareholder (in the same order as `share`).
+    pub fn share_bytes(&self, data: &[u8]) -> Result<Vec<Vec<i64>>, BytesError> {
+        let per_element = bytes_per_element(self.prime);
+
+        let mut hasher = Sha3_256::new();
+        hasher.update(data);
+        let digest = hasher.finalize();
+
+        let length_bytes = (data.len() as u64).to_be_bytes();
+        let mut elements = bytes_to_elements(&length_bytes, per_element);
+        elements.extend(bytes_to_elements(data, per_element));
+        elements.extend(bytes_to_elements(&digest, per_element));
+
+        // pad so the flattened elements split evenly into groups of secret_count
+        let pad = (self.secret_count - (elements.len() % self.secret_count)) % self.secret_count;
+        elements.extend(vec![0; pad]);
+
+        let mut shares_by_holder = vec![Vec::new(); self.share_count];
+        for group in elements.chunks(self.secret_count) {
+            let group_shares = self.share(group)?;
+            for (holder_shares, share) in shares_by_holder.iter_mut().zip(group_shares) {
+                holder_shares.push(share);
+            }
+        }
+        Ok(shares_by_holder)
+    }
+
+    /// Reconstructs the original byte slice from shares produced by
+    /// `share_bytes`, as identified by `indices`.
+    ///
+    /// Returns `Err(BytesError::DigestMismatch)` if the reconstructed
+    /// plaintext does not match the digest shared alongside it.
+    pub fn reconstruct_bytes(&self,
+                              indices: &[usize],
+                              shares: &[Vec<i64>])
+                              -> Result<Vec<u8>, BytesError> {
+        if shares.is_empty() {
+            return Err(BytesError::NoShares);
+        }
+        let group_count = shares[0].len();
+        if shares.iter().any(|s| s.len() != group_count) {
+            return Err(BytesError::InconsistentShareLengths);
+        }
+
+        let per_element = bytes_per_element(self.prime);
+
+        let mut elements = Vec::new();
+        for group in 0..group_count {
+            let group_shares: Vec<i64> = shares.iter().map(|s| s[group]).collect();
+            elements.extend(self.reconstruct(indices, &group_shares)?);
+        }
+        let elements = positivise(&elements, self.prime);
+
+        let length_element_count = element_count_for(LENGTH_SIZE, per_element);
+        if length_element_count > elements.len() {
+            return Err(BytesError::InvalidLength);
+        }
+        let mut length_bytes = elements_to_bytes(&elements[0..length_element_count], per_element);
+        length_bytes.truncate(LENGTH_SIZE);
+        let mut length_array = [0u8; LENGTH_SIZE];
+        length_array.copy_from_slice(&length_bytes);
+        let data_len = u64::from_be_bytes(length_array) as usize;
+
+        // bound data_len by what the reconstructed elements could possibly
+        // hold before doing arithmetic on it, so a wrong/corrupted length
+        // prefix errors out instead of overflowing the element-count math
+        let payload_start = length_element_count;
+        let max_payload_bytes = (elements.len() - payload_start).saturating_mul(per_element);
+        if data_len > max_payload_bytes {
+            return Err(BytesError::InvalidLength);
+        }
+
+        let payload_element_count = element_count_for(data_len, per_element);
+        let digest_element_count = element_count_for(DIGEST_SIZE, per_element);
+        let digest_start = payload_start + payload_element_count;
+        if digest_start + digest_element_count > elements.len() {
+            return Err(BytesError::InvalidLength);
+        }
+
+        let payload_elements = &elements[payload_start..payload_start + payload_element_count];
+        let mut data = elements_to_bytes(payload_elements, per_element);
+        data.truncate(data_len);
+
+        let digest_elements = &elements[digest_start..digest_start + digest_element_count];
+        let mut digest = elements_to_bytes(digest_elements, per_element);
+        digest.truncate(DIGEST_SIZE);
+
+        let mut hasher = Sha3_256::new();
+        hasher.update(&data);
+        if hasher.finalize().as_slice() != digest.as_slice() {
+            return Err(BytesError::DigestMismatch);
+        }
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use packed::{PSS_4_8_3, PSS_4_26_3};
+
+    #[test]
+    fn test_share_reconstruct_bytes() {
+        let ref pss = PSS_4_26_3;
+        let data = b"the quick brown fox jumps over the lazy dog";
+
+        let shares = pss.share_bytes(data).unwrap();
+
+        let indices: Vec<usize> = (0..pss.reconstruct_limit()).collect();
+        let selected: Vec<Vec<i64>> = indices.iter().map(|&i| shares[i].clone()).collect();
+        let recovered = pss.reconstruct_bytes(&indices, &selected).unwrap();
+
+        assert_eq!(recovered, data);
+    }
+
+    #[test]
+    fn test_reconstruct_bytes_detects_corruption() {
+        let ref pss = PSS_4_26_3;
+        let data = b"the quick brown fox jumps over the lazy dog";
+
+        // group 3 falls entirely within the payload (the length prefix
+        // occupies groups 0-2), so this only corrupts payload bytes and
+        // should be caught by the digest check
+        let mut shares = pss.share_bytes(data).unwrap();
+        shares[0][3] += 1;
+
+        let indices: Vec<usize> = (0..pss.reconstruct_limit()).collect();
+        let selected: Vec<Vec<i64>> = indices.iter().map(|&i| shares[i].clone()).collect();
+
+        assert_eq!(pss.reconstruct_bytes(&indices, &selected), Err(BytesError::DigestMismatch));
+    }
+
+    #[test]
+    fn test_reconstruct_bytes_rejects_corrupted_length() {
+        let ref pss = PSS_4_26_3;
+        let data = b"the quick brown fox jumps over the lazy dog";
+
+        // group 0 covers the most significant bytes of the length prefix,
+        // so corrupting it turns data_len into a huge, implausible value
+        // instead of merely corrupting the payload or digest
+        let mut shares = pss.share_bytes(data).unwrap();
+        shares[0][0] += 1;
+
+        let indices: Vec<usize> = (0..pss.reconstruct_limit()).collect();
+        let selected: Vec<Vec<i64>> = indices.iter().map(|&i| shares[i].clone()).collect();
+
+        assert_eq!(pss.reconstruct_bytes(&indices, &selected), Err(BytesError::InvalidLength));
+    }
+
+    #[test]
+    fn test_reconstruct_bytes_rejects_bad_input() {
+        let ref pss = PSS_4_26_3;
+
+        assert_eq!(pss.reconstruct_bytes(&[], &[]), Err(BytesError::NoShares));
+
+        let mismatched = vec![vec![1, 2], vec![1]];
+        assert_eq!(pss.reconstruct_bytes(&[0, 1], &mismatched),
+                   Err(BytesError::InconsistentShareLengths));
+    }
+
+    #[test]
+    fn test_share_reconstruct_bytes_longer_than_prime() {
+        let ref pss = PSS_4_8_3;
+        let data = vec![7u8; pss.prime as usize + 10];
+
+        let shares = pss.share_bytes(&data).unwrap();
+
+        let indices: Vec<usize> = (0..pss.reconstruct_limit()).collect();
+        let selected: Vec<Vec<i64>> = indices.iter().map(|&i| shares[i].clone()).collect();
+        let recovered = pss.reconstruct_bytes(&indices, &selected).unwrap();
+
+        assert_eq!(recovered, data);
+    }
+
+}
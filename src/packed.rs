@@ -9,7 +9,23 @@
 //! Packed variant of secret sharing, allowing to share efficiently several values together.
 
 use numtheory::{mod_pow, fft2_inverse, fft3};
-use rand;
+use rand::{self, RngCore};
+
+/// Draws a uniformly distributed value in `0..bound` from `rng`.
+///
+/// Uses rejection sampling instead of `next_u64() % bound` so that every
+/// value in the range is equally likely: naive modulo sampling is biased
+/// towards the low end of the range whenever `bound` doesn't evenly divide
+/// `u64::max_value() + 1`.
+fn sample_below(rng: &mut dyn RngCore, bound: u64) -> u64 {
+    let zone = u64::max_value() - u64::max_value() % bound;
+    loop {
+        let v = rng.next_u64();
+        if v < zone {
+            return v % bound;
+        }
+    }
+}
 
 /// Packed variant of the secret sharing.
 ///
@@ -61,6 +77,45 @@ pub struct PackedSecretSharing {
     pub omega_shares: i64,
 }
 
+/// Errors returned by `PackedSecretSharing` when it is misused, in place of
+/// the panics an embedding application (e.g. a server) cannot recover from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PssError {
+    /// `secrets.len()` did not equal `secret_count`
+    WrongSecretsCount {
+        /// expected length, i.e. `secret_count`
+        expected: usize,
+        /// length that was actually given
+        actual: usize,
+    },
+    /// `shares.len()` did not equal `indices.len()`
+    DifferentLengthShares,
+    /// fewer shares were given than required: `reconstruct_limit()` for
+    /// reconstruction, `degree_reduction_share_count()` for `reduce_degree`
+    NotEnoughShares {
+        /// minimum number of shares required
+        needed: usize,
+        /// number of shares that were actually given
+        given: usize,
+    },
+    /// an index was `>= share_count`, so it cannot name a share
+    ShareIndexTooLarge {
+        /// the offending index
+        index: usize,
+        /// number of shares that exist, i.e. `share_count`
+        share_count: usize,
+    },
+    /// two or more shares were given for the same index, which breaks
+    /// interpolation (it requires distinct evaluation points)
+    SharesWithSameIndices,
+    /// `new`/`new_with_min_size` was given a `threshold`/`secret_count`/
+    /// `share_count` combination that cannot be realised: `threshold +
+    /// secret_count + 1` must be a power of two, `share_count + 1` must be
+    /// a power of three, and `min_size` must be at least `share_count +
+    /// secret_count + 1`
+    InvalidShapeParameters,
+}
+
 /// Example of tiny PSS settings, for sharing 3 secrets 8 ways, with
 /// a security threshold of 4.
 pub static PSS_4_8_3: PackedSecretSharing = PackedSecretSharing {
@@ -115,11 +170,10 @@ impl PackedSecretSharing {
 
     /// Computes shares for the vector of secrets.
     ///
-    /// It is assumed that `secret` is equal in len to `secret_count` (the
-    /// code will assert otherwise). It is safe to pad with anything, including
-    /// zeros.
-    pub fn share(&self, secrets: &[i64]) -> Vec<i64> {
-        assert_eq!(secrets.len(), self.secret_count);
+    /// `secrets` must be equal in length to `secret_count`. It is safe to
+    /// pad with anything, including zeros.
+    pub fn share(&self, secrets: &[i64]) -> Result<Vec<i64>, PssError> {
+        self.check_secrets_count(secrets)?;
         // sample polynomial
         let mut poly = self.sample_polynomial(secrets);
         // .. and extend it
@@ -130,18 +184,53 @@ impl PackedSecretSharing {
         shares.remove(0);
         // return
         assert_eq!(shares.len(), self.share_count);
-        shares
+        Ok(shares)
+    }
+
+    /// Like `share`, but draws the polynomial's blinding coefficients from
+    /// `rng` instead of `OsRng`.
+    ///
+    /// This lets callers supply a seeded CSPRNG for reproducible tests, or a
+    /// hardware RNG in production, instead of relying on `share`'s use of
+    /// OS entropy, which is unavailable (and panics) in some sandboxed
+    /// environments.
+    pub fn share_with_rng(&self, secrets: &[i64], rng: &mut dyn RngCore) -> Result<Vec<i64>, PssError> {
+        self.check_secrets_count(secrets)?;
+        // sample polynomial
+        let mut poly = self.sample_polynomial_with_rng(secrets, rng);
+        // .. and extend it
+        poly.extend(vec![0; self.share_count + 1 - self.reconstruct_limit()]);
+        // evaluate polynomial to generate shares
+        let mut shares = self.evaluate_polynomial(poly);
+        // .. but remove first element since it should not be used as a share (it's the unused point 1, always 0)
+        shares.remove(0);
+        // return
+        assert_eq!(shares.len(), self.share_count);
+        Ok(shares)
+    }
+
+    fn check_secrets_count(&self, secrets: &[i64]) -> Result<(), PssError> {
+        if secrets.len() != self.secret_count {
+            return Err(PssError::WrongSecretsCount {
+                expected: self.secret_count,
+                actual: secrets.len(),
+            });
+        }
+        Ok(())
     }
 
     fn sample_polynomial(&self, secrets: &[i64]) -> Vec<i64> {
-        // sample randomness
-        //  - for cryptographic use we should use OsRng as dictated here
-        //    https://doc.rust-lang.org/rand/rand/index.html#cryptographic-security
-        use rand::distributions::Sample;
-        let mut range = rand::distributions::range::Range::new(0, self.prime - 1);
+        // for cryptographic use we should use OsRng as dictated here
+        // https://doc.rust-lang.org/rand/rand/index.html#cryptographic-security
         let mut rng = rand::OsRng::new().unwrap();
+        self.sample_polynomial_with_rng(secrets, &mut rng)
+    }
+
+    fn sample_polynomial_with_rng(&self, secrets: &[i64], rng: &mut dyn RngCore) -> Vec<i64> {
+        // sample randomness for the `threshold` blinding coefficients
+        let bound = (self.prime - 1) as u64;
         let randomness: Vec<i64> =
-            (0..self.threshold).map(|_| range.sample(&mut rng) as i64).collect();
+            (0..self.threshold).map(|_| sample_below(rng, bound) as i64).collect();
         // recover polynomial
         let coefficients = self.recover_polynomial(secrets, randomness);
         coefficients
@@ -168,16 +257,15 @@ impl PackedSecretSharing {
 
     /// Reconstruct the secret vector from enough shares.
     ///
-    /// `indices` and `shares` must be of the same size, and strictly more than
-    /// `threshold` (it will assert if otherwise).
+    /// `indices` and `shares` must be of the same size, and at least
+    /// `reconstruct_limit()` long.
     ///
     /// `indices` is the rank of the known shares from the `share` method
     /// output, while `values` are the actual values of these shares.
     ///
     /// The result is of length `secret_count`.
-    pub fn reconstruct(&self, indices: &[usize], shares: &[i64]) -> Vec<i64> {
-        assert!(shares.len() == indices.len());
-        assert!(shares.len() >= self.reconstruct_limit());
+    pub fn reconstruct(&self, indices: &[usize], shares: &[i64]) -> Result<Vec<i64>, PssError> {
+        self.check_indices(indices, shares.len())?;
         let shares_points: Vec<i64> =
             indices.iter().map(|&x| mod_pow(self.omega_shares, x as u32 + 1, self.prime)).collect();
         // interpolate using Newton's method
@@ -191,7 +279,145 @@ impl PackedSecretSharing {
             .map(|point| newton_evaluate(&poly, point, self.prime))
             .take(self.secret_count)
             .collect();
-        secrets
+        Ok(secrets)
+    }
+
+    /// Like `reconstruct`, but reconstructs in `O(n.log(n))` via the inverse
+    /// FFT whenever `indices` is the full, contiguous set of share points
+    /// `0..share_count` -- the common "I have all the shares" case. Falls
+    /// back to (quadratic) Newton interpolation for any other, partial set
+    /// of indices.
+    pub fn reconstruct_fast(&self, indices: &[usize], shares: &[i64]) -> Result<Vec<i64>, PssError> {
+        self.check_indices(indices, shares.len())?;
+
+        let is_full_share_set = shares.len() == self.share_count &&
+            indices.iter().enumerate().all(|(i, &x)| i == x);
+        if !is_full_share_set {
+            return self.reconstruct(indices, shares);
+        }
+
+        use numtheory::{fft3_inverse, mod_evaluate_polynomial};
+        // reinsert the value at the unused point 1 (always 0, see `share`)
+        // to rebuild the full evaluation vector on the 3^n roots of unity
+        let mut values: Vec<i64> = vec![0];
+        values.extend(shares);
+        let poly = fft3_inverse(&values, self.omega_shares, self.prime);
+
+        // evaluate at the secret points to recover the secrets
+        let secrets = (1..self.reconstruct_limit())
+            .map(|e| mod_pow(self.omega_secrets, e as u32, self.prime))
+            .map(|point| mod_evaluate_polynomial(&poly, point, self.prime))
+            .take(self.secret_count)
+            .collect();
+        Ok(secrets)
+    }
+
+    /// Number of pointwise-product shares needed by `reduce_degree`: a
+    /// product of two sharings of degree `reconstruct_limit() - 1` lies on a
+    /// polynomial of degree `2 * (reconstruct_limit() - 1)`, so
+    /// reconstructing (or reducing) it takes twice
+    /// `reconstruct_limit()` minus one shares, instead of `reconstruct_limit()`.
+    pub fn degree_reduction_share_count(&self) -> usize {
+        2 * self.reconstruct_limit() - 1
+    }
+
+    /// Precomputes the recombination vectors used by `reduce_degree`, one
+    /// per secret slot.
+    ///
+    /// Each slot must be recombined independently: collapsing every slot's
+    /// Lagrange weights into a single scalar (as a naive generalisation of
+    /// the classical single-secret Ben-Or/GMW truncation would) dumps the
+    /// sum of all `secret_count` products into one slot instead of keeping
+    /// them separate.
+    ///
+    /// Depends only on `self`'s parameters (not on any secret data), so it
+    /// is safe to compute once and reuse across many calls to
+    /// `reduce_degree`.
+    ///
+    /// `recombination_vectors()[k][i]` is the Lagrange weight of share `i`
+    /// when reconstructing secret slot `k` alone from
+    /// `degree_reduction_share_count()` shares of a degree-doubled sharing.
+    pub fn recombination_vectors(&self) -> Vec<Vec<i64>> {
+        let m = self.degree_reduction_share_count();
+        let indices: Vec<usize> = (0..m).collect();
+        let mut vectors = vec![vec![0; m]; self.secret_count];
+        for i in 0..m {
+            let mut unit = vec![0; m];
+            unit[i] = 1;
+            // reconstruct is linear in the shares, so reconstructing a unit
+            // vector gives exactly the coefficients of share `i` in every
+            // one of the secret_count outputs, without collapsing them
+            let contributions = self.reconstruct(&indices, &unit).unwrap();
+            for (k, &contribution) in contributions.iter().enumerate() {
+                vectors[k][i] = contribution;
+            }
+        }
+        vectors
+    }
+
+    /// Reduces the degree of a pointwise product of two sharings back down
+    /// to `reconstruct_limit() - 1`, so it can be fed into further additions and
+    /// multiplications (see `test_share_multiplicative_homomorphism` for why
+    /// this is otherwise a dead end for chained computation).
+    ///
+    /// Runs the classical Ben-Or/GMW-style re-sharing truncation protocol,
+    /// independently per secret slot `k`: every shareholder re-shares its
+    /// product share with a fresh sharing (via `share`) holding that
+    /// share at slot `k` only (zero elsewhere), and those sub-shares are
+    /// recombined, via `recombination_vectors()[k]`, into a sharing of
+    /// slot `k` alone (zero elsewhere). Summing the per-slot results over
+    /// all `k` gives a fresh sharing, of the same degree as `share`'s, of the whole
+    /// product vector, since each slot's result only ever contributes to
+    /// its own slot.
+    ///
+    /// `product_shares` must hold at least `degree_reduction_share_count()`
+    /// shares, for share indices `0..degree_reduction_share_count()`.
+    pub fn reduce_degree(&self, product_shares: &[i64]) -> Result<Vec<i64>, PssError> {
+        let needed = self.degree_reduction_share_count();
+        if product_shares.len() < needed {
+            return Err(PssError::NotEnoughShares { needed: needed, given: product_shares.len() });
+        }
+
+        let lambda = self.recombination_vectors();
+
+        let mut new_shares = vec![0; self.share_count];
+        for k in 0..self.secret_count {
+            for (i, &h_i) in product_shares[0..needed].iter().enumerate() {
+                let mut secret = vec![0; self.secret_count];
+                secret[k] = h_i;
+                let sub_shares = self.share(&secret)?;
+                let lambda_i = lambda[k][i];
+                for (new_share, sub_share) in new_shares.iter_mut().zip(sub_shares) {
+                    *new_share = (*new_share + lambda_i * sub_share) % self.prime;
+                }
+            }
+        }
+
+        Ok(new_shares)
+    }
+
+    fn check_indices(&self, indices: &[usize], shares_len: usize) -> Result<(), PssError> {
+        if shares_len != indices.len() {
+            return Err(PssError::DifferentLengthShares);
+        }
+        if shares_len < self.reconstruct_limit() {
+            return Err(PssError::NotEnoughShares {
+                needed: self.reconstruct_limit(),
+                given: shares_len,
+            });
+        }
+        if let Some(&index) = indices.iter().find(|&&index| index >= self.share_count) {
+            return Err(PssError::ShareIndexTooLarge {
+                index: index,
+                share_count: self.share_count,
+            });
+        }
+        let mut sorted = indices.to_vec();
+        sorted.sort();
+        if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(PssError::SharesWithSameIndices);
+        }
+        Ok(())
     }
 }
 
@@ -230,7 +456,7 @@ mod tests {
 
         // do sharing
         let secrets = vec![5, 6, 7];
-        let mut shares = pss.share(&secrets);
+        let mut shares = pss.share(&secrets).unwrap();
 
         // manually recover secrets
         use numtheory::{fft3_inverse, mod_evaluate_polynomial};
@@ -254,7 +480,7 @@ mod tests {
     fn test_large_share() {
         let ref pss = PSS_155_19682_100;
         let secrets = vec![5 ; pss.secret_count];
-        let shares = pss.share(&secrets);
+        let shares = pss.share(&secrets).unwrap();
         assert_eq!(shares.len(), pss.share_count);
     }
 
@@ -262,18 +488,37 @@ mod tests {
     fn test_share_reconstruct() {
         let ref pss = PSS_4_26_3;
         let secrets = vec![5, 6, 7];
-        let shares = pss.share(&secrets);
+        let shares = pss.share(&secrets).unwrap();
 
         use numtheory::positivise;
 
         // reconstruction must work for all shares
         let indices: Vec<usize> = (0..shares.len()).collect();
-        let recovered_secrets = pss.reconstruct(&indices, &shares);
+        let recovered_secrets = pss.reconstruct(&indices, &shares).unwrap();
         assert_eq!(positivise(&recovered_secrets, pss.prime), secrets);
 
         // .. and for only sufficient shares
         let indices: Vec<usize> = (0..pss.reconstruct_limit()).collect();
-        let recovered_secrets = pss.reconstruct(&indices, &shares[0..pss.reconstruct_limit()]);
+        let recovered_secrets = pss.reconstruct(&indices, &shares[0..pss.reconstruct_limit()]).unwrap();
+        assert_eq!(positivise(&recovered_secrets, pss.prime), secrets);
+    }
+
+    #[test]
+    fn test_share_reconstruct_fast() {
+        let ref pss = PSS_4_26_3;
+        let secrets = vec![5, 6, 7];
+        let shares = pss.share(&secrets).unwrap();
+
+        use numtheory::positivise;
+
+        // fast path: reconstruction from the full, contiguous share set
+        let indices: Vec<usize> = (0..shares.len()).collect();
+        let recovered_secrets = pss.reconstruct_fast(&indices, &shares).unwrap();
+        assert_eq!(positivise(&recovered_secrets, pss.prime), secrets);
+
+        // falls back to Newton interpolation for a partial set of indices
+        let indices: Vec<usize> = (0..pss.reconstruct_limit()).collect();
+        let recovered_secrets = pss.reconstruct_fast(&indices, &shares[0..pss.reconstruct_limit()]).unwrap();
         assert_eq!(positivise(&recovered_secrets, pss.prime), secrets);
     }
 
@@ -283,8 +528,8 @@ mod tests {
 
         let secrets_1 = vec![1, 2, 3];
         let secrets_2 = vec![4, 5, 6];
-        let shares_1 = pss.share(&secrets_1);
-        let shares_2 = pss.share(&secrets_2);
+        let shares_1 = pss.share(&secrets_1).unwrap();
+        let shares_2 = pss.share(&secrets_2).unwrap();
 
         // add shares pointwise
         let shares_sum: Vec<i64> =
@@ -294,7 +539,7 @@ mod tests {
         let reconstruct_limit = pss.reconstruct_limit();
         let indices: Vec<usize> = (0..reconstruct_limit).collect();
         let shares = &shares_sum[0..reconstruct_limit];
-        let recovered_secrets = pss.reconstruct(&indices, shares);
+        let recovered_secrets = pss.reconstruct(&indices, shares).unwrap();
 
         use numtheory::positivise;
         assert_eq!(positivise(&recovered_secrets, pss.prime), vec![5, 7, 9]);
@@ -306,8 +551,8 @@ mod tests {
 
         let secrets_1 = vec![1, 2, 3];
         let secrets_2 = vec![4, 5, 6];
-        let shares_1 = pss.share(&secrets_1);
-        let shares_2 = pss.share(&secrets_2);
+        let shares_1 = pss.share(&secrets_1).unwrap();
+        let shares_2 = pss.share(&secrets_2).unwrap();
 
         // multiply shares pointwise
         let shares_product: Vec<i64> =
@@ -317,12 +562,109 @@ mod tests {
         let reconstruct_limit = pss.reconstruct_limit() * 2 - 1;
         let indices: Vec<usize> = (0..reconstruct_limit).collect();
         let shares = &shares_product[0..reconstruct_limit];
-        let recovered_secrets = pss.reconstruct(&indices, shares);
+        let recovered_secrets = pss.reconstruct(&indices, shares).unwrap();
 
         use numtheory::positivise;
         assert_eq!(positivise(&recovered_secrets, pss.prime), vec![4, 10, 18]);
     }
 
+    #[test]
+    fn test_reduce_degree() {
+        let ref pss = PSS_4_26_3;
+
+        let secrets_1 = vec![1, 2, 3];
+        let secrets_2 = vec![4, 5, 6];
+        let shares_1 = pss.share(&secrets_1).unwrap();
+        let shares_2 = pss.share(&secrets_2).unwrap();
+
+        // multiply shares pointwise, giving a degree-2*(reconstruct_limit()-1) sharing
+        let shares_product: Vec<i64> =
+            shares_1.iter().zip(shares_2).map(|(a, b)| (a * b) % pss.prime).collect();
+
+        let reduced = pss.reduce_degree(&shares_product).unwrap();
+
+        // unlike `shares_product`, the reduced sharing reconstructs with
+        // only `reconstruct_limit()` shares
+        use numtheory::positivise;
+        let limit = pss.reconstruct_limit();
+        let indices: Vec<usize> = (0..limit).collect();
+        let recovered = pss.reconstruct(&indices, &reduced[0..limit]).unwrap();
+        assert_eq!(positivise(&recovered, pss.prime), vec![4, 10, 18]);
+    }
+
+    #[test]
+    fn test_share_errors() {
+        let ref pss = PSS_4_26_3;
+        assert_eq!(pss.share(&[1, 2]),
+                   Err(PssError::WrongSecretsCount { expected: 3, actual: 2 }));
+    }
+
+    #[test]
+    fn test_reconstruct_errors() {
+        let ref pss = PSS_4_26_3;
+        let secrets = vec![5, 6, 7];
+        let shares = pss.share(&secrets).unwrap();
+        let limit = pss.reconstruct_limit();
+
+        let indices: Vec<usize> = (0..limit).collect();
+        assert_eq!(pss.reconstruct(&indices, &shares[0..limit - 1]),
+                   Err(PssError::DifferentLengthShares));
+
+        assert_eq!(pss.reconstruct(&indices[0..limit - 1], &shares[0..limit - 1]),
+                   Err(PssError::NotEnoughShares { needed: limit, given: limit - 1 }));
+
+        let mut bad_indices = indices.clone();
+        bad_indices[0] = pss.share_count;
+        assert_eq!(pss.reconstruct(&bad_indices, &shares[0..limit]),
+                   Err(PssError::ShareIndexTooLarge { index: pss.share_count, share_count: pss.share_count }));
+
+        let mut dup_indices = indices.clone();
+        dup_indices[0] = dup_indices[1];
+        assert_eq!(pss.reconstruct(&dup_indices, &shares[0..limit]),
+                   Err(PssError::SharesWithSameIndices));
+    }
+
+    /// A trivial deterministic `RngCore`, for tests that need reproducible
+    /// sharing without depending on an external CSPRNG crate.
+    struct FixedRng(u64);
+
+    impl RngCore for FixedRng {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(8) {
+                chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_share_with_rng_is_deterministic() {
+        let ref pss = PSS_4_26_3;
+        let secrets = vec![5, 6, 7];
+
+        let shares_1 = pss.share_with_rng(&secrets, &mut FixedRng(42)).unwrap();
+        let shares_2 = pss.share_with_rng(&secrets, &mut FixedRng(42)).unwrap();
+        assert_eq!(shares_1, shares_2);
+
+        let limit = pss.reconstruct_limit();
+        let indices: Vec<usize> = (0..limit).collect();
+        let recovered = pss.reconstruct(&indices, &shares_1[0..limit]).unwrap();
+        assert_eq!(positivise(&recovered, pss.prime), secrets);
+    }
+
 }
 
 
@@ -434,40 +776,59 @@ pub mod paramgen {
     }
 
     use super::PackedSecretSharing;
+    use super::PssError;
 
     impl PackedSecretSharing {
+        /// Derives a full `PackedSecretSharing` from just its shape, finding
+        /// a prime field of at least `min_size` with suitable roots of
+        /// unity.
+        ///
+        /// Returns `Err(PssError::InvalidShapeParameters)` if `threshold +
+        /// secret_count + 1` is not a power of two, if `share_count + 1` is
+        /// not a power of three, or if `min_size < share_count +
+        /// secret_count + 1`.
         pub fn new_with_min_size(threshold: usize,
                                  secret_count: usize,
                                  share_count: usize,
                                  min_size: usize)
-                                 -> PackedSecretSharing {
+                                 -> Result<PackedSecretSharing, PssError> {
             let n = threshold + secret_count + 1;
             let m = share_count + 1;
 
             let two_power = (n as f64).log(2f64).floor() as u32;
-            assert!(2usize.pow(two_power) == n);
+            if 2usize.pow(two_power) != n {
+                return Err(PssError::InvalidShapeParameters);
+            }
 
             let three_power = (m as f64).log(3f64).floor() as u32;
-            assert!(3usize.pow(three_power) == m);
+            if 3usize.pow(three_power) != m {
+                return Err(PssError::InvalidShapeParameters);
+            }
 
-            assert!(min_size >= share_count + secret_count + 1);
+            if min_size < share_count + secret_count + 1 {
+                return Err(PssError::InvalidShapeParameters);
+            }
 
             let (prime, omega_secrets, omega_shares) = generate_parameters(min_size, n, m);
 
-            PackedSecretSharing {
+            Ok(PackedSecretSharing {
                 threshold: threshold,
                 share_count: share_count,
                 secret_count: secret_count,
                 prime: prime,
                 omega_secrets: omega_secrets,
                 omega_shares: omega_shares,
-            }
+            })
         }
 
+        /// Derives a full `PackedSecretSharing` from just its shape, using
+        /// the smallest `min_size` that can hold `share_count + secret_count
+        /// + threshold + 1` values. See `new_with_min_size` for the error
+        /// conditions.
         pub fn new(threshold: usize,
                    secret_count: usize,
                    share_count: usize)
-                   -> PackedSecretSharing {
+                   -> Result<PackedSecretSharing, PssError> {
             let min_size = share_count + secret_count + threshold + 1;
             Self::new_with_min_size(threshold, secret_count, share_count, min_size)
         }
@@ -475,12 +836,25 @@ pub mod paramgen {
 
     #[test]
     fn test_new() {
-        assert_eq!(PackedSecretSharing::new(155, 100, 728),
+        assert_eq!(PackedSecretSharing::new(155, 100, 728).unwrap(),
                    super::PSS_155_728_100);
-        assert_eq!(PackedSecretSharing::new_with_min_size(4, 3, 8, 200),
+        assert_eq!(PackedSecretSharing::new_with_min_size(4, 3, 8, 200).unwrap(),
                    super::PSS_4_8_3);
-        assert_eq!(PackedSecretSharing::new_with_min_size(4, 3, 26, 200),
+        assert_eq!(PackedSecretSharing::new_with_min_size(4, 3, 26, 200).unwrap(),
                    super::PSS_4_26_3);
     }
 
+    #[test]
+    fn test_new_invalid_shape() {
+        // threshold + secret_count + 1 = 10, not a power of two
+        assert_eq!(PackedSecretSharing::new(6, 3, 8),
+                   Err(PssError::InvalidShapeParameters));
+        // share_count + 1 = 8, not a power of three
+        assert_eq!(PackedSecretSharing::new(4, 3, 7),
+                   Err(PssError::InvalidShapeParameters));
+        // min_size too small to fit share_count + secret_count + 1
+        assert_eq!(PackedSecretSharing::new_with_min_size(4, 3, 26, 10),
+                   Err(PssError::InvalidShapeParameters));
+    }
+
 }